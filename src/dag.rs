@@ -0,0 +1,303 @@
+//! Dependency-aware scheduling for `ServiceManager`
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use log::*;
+
+use crate::error::ServiceError;
+use crate::service::Service;
+use crate::status::ServiceStatus;
+
+const DAG_SERVICE_NAME: &'static str = "srvzio::Dag";
+
+/// A `Service` registered together with the names of the `Service`s it depends on.
+struct DagNode {
+  name: &'static str,
+  deps: Vec<&'static str>,
+  service: Arc<Mutex<Box<Service + Send>>>,
+}
+
+/// A group of `Service`s, wired together by named dependencies instead of plain registration
+/// order, and scheduled accordingly.
+///
+/// Unlike `ServiceManager`'s plain registration list, a `Dag` starts every `Service` whose
+/// dependencies are already `Started` concurrently, on its own thread: independent subsystems
+/// come up in parallel instead of one-at-a-time, while dependents still wait on their
+/// prerequisites.
+pub struct Dag {
+  nodes: Vec<DagNode>,
+}
+
+impl Dag {
+
+  /// Constructor
+  pub fn new() -> Self {
+    Dag {
+      nodes: Vec::new(),
+    }
+  }
+
+  /// Whether any `Service` has been registered in this `Dag`
+  pub fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// Register an instance of `Service`, alongside the names of the `Service`s it depends on.
+  ///
+  /// # Parameters
+  ///
+  /// * `service_box`: a `Box` containing an instance of implementation of the `Service` trait;
+  ///   it must be `Send`, since it may be started on a dedicated thread
+  /// * `deps`: the `name()`s of the `Service`s that must be `Started` before this one starts; these
+  ///   may name other `Dag` nodes, or names resolved externally at `start_and_await`/
+  ///   `stop_and_await` time via `external_names`
+  pub fn register(&mut self, service_box: Box<Service + Send>, deps: &[&'static str]) {
+    let name = service_box.as_ref().name();
+    debug!("Registering (DAG): {} deps={:?}", name, deps);
+    self.nodes.push(DagNode {
+      name,
+      deps: deps.to_vec(),
+      service: Arc::new(Mutex::new(service_box)),
+    });
+  }
+
+  /// Take a snapshot of the status of every registered `Service`
+  pub fn statuses(&self) -> Vec<(&'static str, ServiceStatus)> {
+    self.nodes
+      .iter()
+      .map(|node| (node.name, node.service.lock().unwrap().status()))
+      .collect()
+  }
+
+  /// Kahn's algorithm: returns the node indices in topological order, or a `ServiceError` if a
+  /// node depends on a name that is neither another DAG node nor in `external_names`, or the
+  /// graph contains a cycle.
+  ///
+  /// `external_names` are the names of `ServiceManager`'s plainly- and supervised-registered
+  /// `Service`s: since the whole DAG is only started after those have already started (see
+  /// `ServiceManager::start_and_await`), a dependency on one of them is always already satisfied
+  /// and contributes no in-degree.
+  fn topological_order(&self, external_names: &HashSet<&'static str>) -> Result<Vec<usize>, ServiceError> {
+    let total = self.nodes.len();
+    let index_of: HashMap<&str, usize> = self.nodes
+      .iter()
+      .enumerate()
+      .map(|(i, node)| (node.name, i))
+      .collect();
+
+    let mut in_degree = vec![0usize; total];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); total];
+    for (i, node) in self.nodes.iter().enumerate() {
+      for dep in &node.deps {
+        if let Some(&dep_index) = index_of.get(dep) {
+          dependents[dep_index].push(i);
+          in_degree[i] += 1;
+        } else if !external_names.contains(dep) {
+          return Err(ServiceError::new(
+            node.name, format!("depends on unregistered service `{}`", dep)
+          ));
+        }
+      }
+    }
+
+    let mut ready: VecDeque<usize> = (0..total).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(total);
+    while let Some(i) = ready.pop_front() {
+      order.push(i);
+      for &dependent in &dependents[i] {
+        in_degree[dependent] -= 1;
+        if in_degree[dependent] == 0 {
+          ready.push_back(dependent);
+        }
+      }
+    }
+
+    if order.len() != total {
+      return Err(ServiceError::new(DAG_SERVICE_NAME, "dependency graph contains a cycle"));
+    }
+
+    Ok(order)
+  }
+
+  /// Start every registered `Service`, honoring declared dependencies.
+  ///
+  /// Services whose dependencies are all satisfied are started concurrently, each on its own
+  /// thread; a service is only launched once every one of its declared dependencies has finished
+  /// starting. Should any `Service` fail to start, every `Service` that did start is `stop()`ped,
+  /// in reverse topological order, before the original error is returned.
+  ///
+  /// `external_names` are the names of `ServiceManager`'s plainly- and supervised-registered
+  /// `Service`s, which have already started by the time this is called: a dependency naming one
+  /// of them is treated as already satisfied, rather than rejected as unregistered.
+  pub fn start_and_await(&mut self, external_names: &HashSet<&'static str>) -> Result<(), ServiceError> {
+    if self.nodes.is_empty() {
+      return Ok(());
+    }
+
+    let order = self.topological_order(external_names)?;
+    let total = self.nodes.len();
+    let index_of: HashMap<&str, usize> = self.nodes
+      .iter()
+      .enumerate()
+      .map(|(i, node)| (node.name, i))
+      .collect();
+
+    // `None` while a node hasn't finished starting yet, `Some(true)`/`Some(false)` once it has,
+    // successfully or not (a node whose dependency failed is marked `Some(false)` too, without
+    // ever being started).
+    let completions: Vec<Arc<(Mutex<Option<bool>>, Condvar)>> = (0..total)
+      .map(|_| Arc::new((Mutex::new(None), Condvar::new())))
+      .collect();
+    let errors: Vec<Arc<Mutex<Option<ServiceError>>>> = (0..total)
+      .map(|_| Arc::new(Mutex::new(None)))
+      .collect();
+
+    let handles: Vec<_> = (0..total).map(|i| {
+      let service = self.nodes[i].service.clone();
+      let name = self.nodes[i].name;
+      let completion = completions[i].clone();
+      let error_slot = errors[i].clone();
+      // External deps (already started before the DAG runs) contribute no in-degree, and so have
+      // no completion to wait on; only DAG-internal deps need one.
+      let dep_completions: Vec<_> = self.nodes[i].deps
+        .iter()
+        .filter_map(|dep| index_of.get(dep).map(|&idx| completions[idx].clone()))
+        .collect();
+
+      thread::spawn(move || {
+        let mut deps_started = true;
+        for dep_completion in &dep_completions {
+          let (lock, condvar) = &**dep_completion;
+          let mut done = lock.lock().unwrap();
+          while done.is_none() {
+            done = condvar.wait(done).unwrap();
+          }
+          if *done == Some(false) {
+            deps_started = false;
+          }
+        }
+
+        let started = if !deps_started {
+          warn!("{} skipped: a dependency failed to start", name);
+          false
+        } else {
+          match service.lock().unwrap().start_and_await() {
+            Ok(()) => true,
+            Err(err) => {
+              error!("{} failed to start: {}", name, err);
+              *error_slot.lock().unwrap() = Some(err);
+              false
+            }
+          }
+        };
+
+        let (lock, condvar) = &*completion;
+        *lock.lock().unwrap() = Some(started);
+        condvar.notify_all();
+      })
+    }).collect();
+
+    for handle in handles {
+      let _ = handle.join();
+    }
+
+    let first_failure = order.iter()
+      .find(|&&i| *completions[i].0.lock().unwrap() == Some(false))
+      .cloned();
+
+    match first_failure {
+      None => Ok(()),
+      Some(failed_index) => {
+        let err = errors[failed_index].lock().unwrap().take()
+          .unwrap_or_else(|| ServiceError::new(self.nodes[failed_index].name, "skipped because a dependency failed to start"));
+
+        for &i in order.iter().rev() {
+          if *completions[i].0.lock().unwrap() == Some(true) {
+            if let Err(rollback_err) = self.nodes[i].service.lock().unwrap().stop_and_await() {
+              error!("{} failed to roll back: {}", self.nodes[i].name, rollback_err);
+            }
+          }
+        }
+
+        Err(err)
+      }
+    }
+  }
+
+  /// Stop every registered `Service`, in reverse topological order.
+  ///
+  /// This never stops early: a `Service` failing to stop does not prevent the ones after it in
+  /// reverse topological order from still being stopped. Every error is logged; the first one
+  /// encountered is what's returned.
+  ///
+  /// `external_names` has the same meaning as in `start_and_await`.
+  pub fn stop_and_await(&mut self, external_names: &HashSet<&'static str>) -> Result<(), ServiceError> {
+    if self.nodes.is_empty() {
+      return Ok(());
+    }
+
+    let order = self.topological_order(external_names)?;
+    let mut first_err = None;
+    for &i in order.iter().rev() {
+      debug!("Stopping (DAG): {}", self.nodes[i].name);
+      if let Err(err) = self.nodes[i].service.lock().unwrap().stop_and_await() {
+        error!("{} failed to stop: {}", self.nodes[i].name, err);
+        if first_err.is_none() {
+          first_err = Some(err);
+        }
+      }
+    }
+    match first_err {
+      Some(err) => Err(err),
+      None => Ok(()),
+    }
+  }
+
+  /// Pause every registered `Service`, in reverse topological order.
+  ///
+  /// Mirrors `stop_and_await`: never stops early, so a `Service` failing to pause does not
+  /// prevent the ones after it in reverse topological order from still being paused. Every error
+  /// is logged; the first one encountered is what's returned.
+  ///
+  /// `external_names` has the same meaning as in `start_and_await`.
+  pub fn pause_and_await(&mut self, external_names: &HashSet<&'static str>) -> Result<(), ServiceError> {
+    if self.nodes.is_empty() {
+      return Ok(());
+    }
+
+    let order = self.topological_order(external_names)?;
+    let mut first_err = None;
+    for &i in order.iter().rev() {
+      debug!("Pausing (DAG): {}", self.nodes[i].name);
+      if let Err(err) = self.nodes[i].service.lock().unwrap().pause_and_await() {
+        error!("{} failed to pause: {}", self.nodes[i].name, err);
+        if first_err.is_none() {
+          first_err = Some(err);
+        }
+      }
+    }
+    match first_err {
+      Some(err) => Err(err),
+      None => Ok(()),
+    }
+  }
+
+  /// Resume every registered `Service`, in topological order.
+  ///
+  /// `external_names` has the same meaning as in `start_and_await`.
+  pub fn resume_and_await(&mut self, external_names: &HashSet<&'static str>) -> Result<(), ServiceError> {
+    if self.nodes.is_empty() {
+      return Ok(());
+    }
+
+    let order = self.topological_order(external_names)?;
+    for &i in order.iter() {
+      debug!("Resuming (DAG): {}", self.nodes[i].name);
+      self.nodes[i].service.lock().unwrap().resume_and_await()?;
+    }
+    Ok(())
+  }
+
+}
@@ -0,0 +1,71 @@
+//! The error produced by a fallible `Service` lifecycle method
+
+use std::error::Error;
+use std::fmt;
+
+/// An error raised by a `Service` while starting, stopping, pausing, or resuming.
+///
+/// Carries enough context to be useful on its own (which service, and why), while still
+/// preserving the original cause, if any, through `source()`.
+#[derive(Debug)]
+pub struct ServiceError {
+  service: &'static str,
+  message: String,
+  source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl ServiceError {
+
+  /// Constructor, for a failure with no underlying cause.
+  ///
+  /// # Parameters
+  ///
+  /// * `service`: the `name()` of the `Service` that failed
+  /// * `message`: a human readable description of the failure
+  pub fn new<M: Into<String>>(service: &'static str, message: M) -> Self {
+    ServiceError {
+      service,
+      message: message.into(),
+      source: None,
+    }
+  }
+
+  /// Constructor, for a failure caused by another error.
+  ///
+  /// # Parameters
+  ///
+  /// * `service`: the `name()` of the `Service` that failed
+  /// * `message`: a human readable description of the failure
+  /// * `source`: the underlying error that caused this `Service` to fail
+  pub fn with_source<M, E>(service: &'static str, message: M, source: E) -> Self
+    where M: Into<String>, E: Into<Box<dyn Error + Send + Sync>> {
+    ServiceError {
+      service,
+      message: message.into(),
+      source: Some(source.into()),
+    }
+  }
+
+  /// The `name()` of the `Service` that produced this error
+  pub fn service(&self) -> &'static str {
+    self.service
+  }
+
+  /// The human readable description of the failure
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+
+}
+
+impl fmt::Display for ServiceError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "service `{}` failed: {}", self.service, self.message)
+  }
+}
+
+impl Error for ServiceError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    self.source.as_ref().map(|source| source.as_ref() as &(dyn Error + 'static))
+  }
+}
@@ -2,10 +2,15 @@ pub mod service;
 pub mod status;
 pub mod manager;
 pub mod utils;
+pub mod error;
+pub mod supervisor;
+pub mod dag;
 
-pub use service::Service;
+pub use service::{Service, TryService};
 pub use status::*;
 pub use manager::ServiceManager;
+pub use error::ServiceError;
+pub use supervisor::{RestartMode, RestartPolicy};
 
 #[cfg(test)]
 mod test;
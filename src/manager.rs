@@ -1,6 +1,13 @@
 //! Where you have services, you need managers
 
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::dag::Dag;
+use crate::error::ServiceError;
 use crate::service::Service;
+use crate::status::ServiceStatus;
+use crate::supervisor::{RestartPolicy, Supervised};
 use crate::utils;
 
 use log::*;
@@ -21,7 +28,9 @@ use log::*;
 /// [DAG diagrams](https://en.wikipedia.org/wiki/Directed_acyclic_graph) of services,
 /// with expressive relationship between them.
 pub struct ServiceManager {
-  services: Vec<Box<Service>>
+  services: Vec<Box<Service + Send>>,
+  supervision_faults: Vec<Arc<Mutex<Option<ServiceError>>>>,
+  dag: Dag,
 }
 
 impl ServiceManager {
@@ -30,6 +39,8 @@ impl ServiceManager {
   pub fn new() -> Self {
     ServiceManager {
       services: Vec::new(),
+      supervision_faults: Vec::new(),
+      dag: Dag::new(),
     }
   }
 
@@ -40,37 +51,124 @@ impl ServiceManager {
   ///
   /// # Parameters
   ///
-  /// * `service_box`: a `Box` containing an instance of implementation of the `Service` trait
-  pub fn register(&mut self, service_box: Box<Service>) {
+  /// * `service_box`: a `Box` containing an instance of implementation of the `Service` trait; it
+  ///   must be `Send`, since `ServiceManager` itself needs to be `Send` (e.g. to be registered,
+  ///   via `register_supervised`, as a sub-`Service` of another `ServiceManager`)
+  pub fn register(&mut self, service_box: Box<Service + Send>) {
     debug!("Registering: {}", service_box.as_ref().name());
     self.services.push(service_box);
   }
 
+  /// Register an instance of `Service`, under supervision.
+  ///
+  /// Once started, a background thread watches the service and, per `policy`, restarts it if it
+  /// stops while the manager still expects it to be running, with exponential backoff, up to a
+  /// restart budget. Exhausting that budget raises a fatal error, retrievable via
+  /// `supervision_faults()`.
+  ///
+  /// The order of registration still matters exactly as it does for `register`: supervision is
+  /// an orthogonal concern layered on top of it.
+  ///
+  /// # Parameters
+  ///
+  /// * `service_box`: a `Box` containing an instance of implementation of the `Service` trait;
+  ///   it must be `Send`, since the supervisor thread calls back into it
+  /// * `policy`: the `RestartPolicy` governing if, and how, this service gets restarted
+  pub fn register_supervised(&mut self, service_box: Box<Service + Send>, policy: RestartPolicy) {
+    let supervised = Supervised::new(service_box, policy);
+    debug!("Registering (supervised): {}", supervised.name());
+    self.supervision_faults.push(supervised.fault_handle());
+    self.services.push(Box::new(supervised));
+  }
+
+  /// Drain the fatal errors raised by supervised services whose restart budget has been
+  /// exhausted, since the last time this was called.
+  pub fn supervision_faults(&self) -> Vec<ServiceError> {
+    self.supervision_faults
+      .iter()
+      .filter_map(|fault| fault.lock().unwrap().take())
+      .collect()
+  }
+
+  /// Register an instance of `Service`, alongside the names of the `Service`s it depends on.
+  ///
+  /// Unlike plain `register`, dependency-registered `Service`s are not tied to registration
+  /// order: `start_and_await` topologically sorts them by their declared dependencies, and
+  /// starts independent ones concurrently, while `stop_and_await` walks the reverse topological
+  /// order. This group is scheduled as a whole, after every plainly- or supervised-registered
+  /// `Service` has started. `pause_and_await`/`resume_and_await` schedule it the same way.
+  ///
+  /// Dependency-registered `Service`s only support the `_and_await` methods: `start()`,
+  /// `await_started()`, `stop()` and `await_stopped()` return an error rather than silently
+  /// ignoring this group, since splitting "start the graph" from "wait for it" doesn't make sense
+  /// once independent nodes may be starting concurrently on their own threads.
+  ///
+  /// # Parameters
+  ///
+  /// * `service_box`: a `Box` containing an instance of implementation of the `Service` trait;
+  ///   it must be `Send`, since it may be started on a dedicated thread
+  /// * `deps`: the `name()`s of the `Service`s that must be `Started` before this one starts; these
+  ///   may name other `register_with_deps` services, or plainly- or supervised-registered ones
+  ///   (which, by the time this group is scheduled, have already started)
+  pub fn register_with_deps(&mut self, service_box: Box<Service + Send>, deps: &[&'static str]) {
+    self.dag.register(service_box, deps);
+  }
+
+  /// Take a snapshot of the status of every registered `Service`, in registration order (plainly-
+  /// and supervised-registered services first, then dependency-registered ones).
+  ///
+  /// Useful to build health endpoints or dashboards that report each registered service's live
+  /// state, without having to hold a reference to each `Service` individually.
+  pub fn statuses(&self) -> Vec<(&'static str, ServiceStatus)> {
+    self.services
+      .iter()
+      .map(|s| (s.name(), s.status()))
+      .chain(self.dag.statuses())
+      .collect()
+  }
+
   /// Wait for the Process to receive a termination signal, then stop this `ServiceManager`.
   ///
   /// It's strongly advised to use this method only onces, for the _root_ `ServiceManager`,
   /// at the end of the `main()` thread.
-  pub fn await_termination_signal_then_stop(&mut self) {
+  pub fn await_termination_signal_then_stop(&mut self) -> Result<(), ServiceError> {
     // Block until the process is terminated by a signal...
-    utils::await_for_process_termination_signal();
+    utils::wait_for_process_termination_signal();
 
     // ... then gracefully shut every service down
-    self.stop_and_await();
+    self.stop_and_await()
   }
 
-  /// Apply the same closure to all contained `Service`s, in order
-  fn apply_ordered<F>(&mut self, closure: F) where F: Fn(&mut Box<Service>) -> () {
-    self.services
-      .iter_mut()
-      .for_each(closure);
+  /// Apply the same fallible closure to all contained `Service`s, in order, stopping at the
+  /// first error.
+  fn apply_ordered<F>(&mut self, mut closure: F) -> Result<(), ServiceError>
+    where F: FnMut(&mut Box<Service + Send>) -> Result<(), ServiceError> {
+    for service in self.services.iter_mut() {
+      closure(service)?;
+    }
+    Ok(())
   }
 
-  /// Apply the same closure to all contained `Service`s, in reverse order
-  fn apply_reversed<F>(&mut self, closure: F) where F: FnMut(&mut Box<Service>) -> () {
-    self.services
-      .iter_mut()
-      .rev()
-      .for_each(closure);
+  /// Apply the same fallible closure to all contained `Service`s, in reverse order.
+  ///
+  /// Unlike `apply_ordered`, this never stops early: it's used for teardown (`stop`, `pause`),
+  /// where a `Service` failing to stop must not leave the ones after it in the reverse order
+  /// un-torn-down. Every error is logged; the first one encountered is what's returned.
+  fn apply_reversed<F>(&mut self, mut closure: F) -> Result<(), ServiceError>
+    where F: FnMut(&mut Box<Service + Send>) -> Result<(), ServiceError> {
+    let mut first_err = None;
+    for service in self.services.iter_mut().rev() {
+      if let Err(err) = closure(service) {
+        error!("{} failed during teardown: {}", service.name(), err);
+        if first_err.is_none() {
+          first_err = Some(err);
+        }
+      }
+    }
+    match first_err {
+      Some(err) => Err(err),
+      None => Ok(()),
+    }
   }
 
 }
@@ -83,57 +181,231 @@ impl Service for ServiceManager {
     SERVICE_MANAGER_SERVICE_NAME
   }
 
-  /// Start all registered `Service`s, in order of registration
-  fn start(&mut self) {
-    self.apply_ordered(|s: &mut Box<Service>| {
+  /// The aggregate status of every `Service` this manager contains.
+  ///
+  /// `Stopped` only if every contained `Service` reports `Stopped` (including the case where
+  /// nothing has been registered at all, mirroring the trait's default); otherwise, the status of
+  /// the first `Service` found not yet `Stopped`, as a representative snapshot of the group's
+  /// transition. This is what lets a `ServiceManager` be itself `register_supervised` as a
+  /// sub-`Service` of another `ServiceManager`: the supervisor only sees `Stopped` once the whole
+  /// subtree is genuinely down.
+  fn status(&self) -> ServiceStatus {
+    self.statuses()
+      .into_iter()
+      .map(|(_, status)| status)
+      .find(|status| *status != ServiceStatus::Stopped)
+      .unwrap_or(ServiceStatus::Stopped)
+  }
+
+  /// Start all plainly- and supervised-registered `Service`s, in order of registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: that group only
+  /// supports `start_and_await` (see `register_with_deps`).
+  fn start(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "start()/await_started() don't support dependency-registered services; use start_and_await() instead"));
+    }
+    self.apply_ordered(|s: &mut Box<Service + Send>| {
       debug!("Starting: {}", s.name());
       s.start()
-    });
+    })
   }
 
-  /// Wait for all registered `Service`s to be started, in order of registration
-  fn await_started(&mut self) {
-    self.apply_ordered(|s: &mut Box<Service>| {
+  /// Wait for all plainly- and supervised-registered `Service`s to be started, in order of
+  /// registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: see `start`.
+  fn await_started(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "start()/await_started() don't support dependency-registered services; use start_and_await() instead"));
+    }
+    self.apply_ordered(|s: &mut Box<Service + Send>| {
       debug!("Awaiting started: {}", s.name());
       s.await_started()
-    });
+    })
   }
 
-  /// Start and then wait for all registered `Service`, in order of registration
+  /// Start and then wait for all registered `Service`, in order of registration, then start and
+  /// wait for every dependency-registered `Service` (see `register_with_deps`), honoring the
+  /// declared dependency graph.
   ///
   /// This is different then calling `start()` and then `await_started()`, because this method
   /// will wait for a `Service` to be started, before moving to the next one.
   ///
+  /// Should any `Service` fail to start, every `Service` that was already started is `stop()`ped,
+  /// in reverse order, before the original error is returned: the group is left either fully up,
+  /// or fully down, never half-broken.
+  ///
   /// This can be used to implement a _gracefull start_.
-  fn start_and_await(&mut self) {
-    self.apply_ordered(|s: &mut Box<Service>| s.start_and_await());
-  }
+  fn start_and_await(&mut self) -> Result<(), ServiceError> {
+    for i in 0..self.services.len() {
+      if let Err(err) = self.services[i].start_and_await() {
+        error!("{} failed to start: {}; rolling back already started services", self.services[i].name(), err);
 
+        for j in (0..i).rev() {
+          if let Err(rollback_err) = self.services[j].stop_and_await() {
+            error!("{} failed to roll back: {}", self.services[j].name(), rollback_err);
+          }
+        }
+
+        return Err(err);
+      }
+    }
 
-  /// Stop all registered `Service`s, in reverse order of registration
-  fn stop(&mut self) {
-    self.apply_reversed(|s: &mut Box<Service>| {
+    let registered_names: HashSet<&'static str> = self.services.iter().map(|s| s.name()).collect();
+    if let Err(err) = self.dag.start_and_await(&registered_names) {
+      error!("dependency graph failed to start: {}; rolling back already started services", err);
+
+      for j in (0..self.services.len()).rev() {
+        if let Err(rollback_err) = self.services[j].stop_and_await() {
+          error!("{} failed to roll back: {}", self.services[j].name(), rollback_err);
+        }
+      }
+
+      return Err(err);
+    }
+
+    Ok(())
+  }
+
+  /// Stop all plainly- and supervised-registered `Service`s, in reverse order of registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: that group only
+  /// supports `stop_and_await` (see `register_with_deps`).
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "stop()/await_stopped() don't support dependency-registered services; use stop_and_await() instead"));
+    }
+    self.apply_reversed(|s: &mut Box<Service + Send>| {
       debug!("Stopping: {}", s.name());
       s.stop()
-    });
+    })
   }
 
-  /// Wait for all registered `Service`s to be stopped, in reverse order of registration
-  fn await_stopped(&mut self) {
-    self.apply_reversed(|s: &mut Box<Service>| {
+  /// Wait for all plainly- and supervised-registered `Service`s to be stopped, in reverse order
+  /// of registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: see `stop`.
+  fn await_stopped(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "stop()/await_stopped() don't support dependency-registered services; use stop_and_await() instead"));
+    }
+    self.apply_reversed(|s: &mut Box<Service + Send>| {
       debug!("Awaiting stopped: {}", s.name());
       s.await_stopped()
-    });
+    })
   }
 
-  /// Stop and then wait for all registered `Service`, in reverse order of registration
+  /// Stop and then wait for every dependency-registered `Service` (see `register_with_deps`), in
+  /// reverse topological order, then stop and wait for all registered `Service`, in reverse order
+  /// of registration.
   ///
   /// This is different then calling `stop()` and then `await_stopped()`, because this method
   /// will wait for a `Service` to be stopped, before moving to the next one.
   ///
   /// This can be used to implement a _gracefull stop_.
-  fn stop_and_await(&mut self) {
-    self.apply_reversed(|s: &mut Box<Service>| s.stop_and_await());
+  fn stop_and_await(&mut self) -> Result<(), ServiceError> {
+    let registered_names: HashSet<&'static str> = self.services.iter().map(|s| s.name()).collect();
+    self.dag.stop_and_await(&registered_names)?;
+    self.apply_reversed(|s: &mut Box<Service + Send>| s.stop_and_await())
+  }
+
+  /// Pause all plainly- and supervised-registered `Service`s, in reverse order of registration
+  ///
+  /// Mirrors `stop`: the services registered last are the ones most likely to depend on the
+  /// ones registered first, so they are suspended first.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: that group only
+  /// supports `pause_and_await` (see `register_with_deps`).
+  fn pause(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "pause()/await_paused() don't support dependency-registered services; use pause_and_await() instead"));
+    }
+    self.apply_reversed(|s: &mut Box<Service + Send>| {
+      debug!("Pausing: {}", s.name());
+      s.pause()
+    })
+  }
+
+  /// Wait for all plainly- and supervised-registered `Service`s to be paused, in reverse order of
+  /// registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: see `pause`.
+  fn await_paused(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "pause()/await_paused() don't support dependency-registered services; use pause_and_await() instead"));
+    }
+    self.apply_reversed(|s: &mut Box<Service + Send>| {
+      debug!("Awaiting paused: {}", s.name());
+      s.await_paused()
+    })
   }
 
-}
\ No newline at end of file
+  /// Pause and then wait for every dependency-registered `Service` (see `register_with_deps`), in
+  /// reverse topological order, then pause and wait for all registered `Service`, in reverse
+  /// order of registration.
+  ///
+  /// This is different then calling `pause()` and then `await_paused()`, because this method
+  /// will wait for a `Service` to be paused, before moving to the next one.
+  ///
+  /// This can be used to implement a _gracefull pause_.
+  fn pause_and_await(&mut self) -> Result<(), ServiceError> {
+    let registered_names: HashSet<&'static str> = self.services.iter().map(|s| s.name()).collect();
+    self.dag.pause_and_await(&registered_names)?;
+    self.apply_reversed(|s: &mut Box<Service + Send>| s.pause_and_await())
+  }
+
+  /// Resume all plainly- and supervised-registered `Service`s, in order of registration
+  ///
+  /// Mirrors `start`: the services registered first are resumed first, so their dependents can
+  /// rely on them being back up.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: that group only
+  /// supports `resume_and_await` (see `register_with_deps`).
+  fn resume(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "resume()/await_resumed() don't support dependency-registered services; use resume_and_await() instead"));
+    }
+    self.apply_ordered(|s: &mut Box<Service + Send>| {
+      debug!("Resuming: {}", s.name());
+      s.resume()
+    })
+  }
+
+  /// Wait for all plainly- and supervised-registered `Service`s to be resumed, in order of
+  /// registration.
+  ///
+  /// Errors out if any `Service` was registered via `register_with_deps`: see `resume`.
+  fn await_resumed(&mut self) -> Result<(), ServiceError> {
+    if !self.dag.is_empty() {
+      return Err(ServiceError::new(SERVICE_MANAGER_SERVICE_NAME,
+        "resume()/await_resumed() don't support dependency-registered services; use resume_and_await() instead"));
+    }
+    self.apply_ordered(|s: &mut Box<Service + Send>| {
+      debug!("Awaiting resumed: {}", s.name());
+      s.await_resumed()
+    })
+  }
+
+  /// Resume and then wait for all registered `Service`, in order of registration, then resume and
+  /// wait for every dependency-registered `Service` (see `register_with_deps`), honoring the
+  /// declared dependency graph.
+  ///
+  /// This is different then calling `resume()` and then `await_resumed()`, because this method
+  /// will wait for a `Service` to be resumed, before moving to the next one.
+  ///
+  /// This can be used to implement a _gracefull resume_.
+  fn resume_and_await(&mut self) -> Result<(), ServiceError> {
+    self.apply_ordered(|s: &mut Box<Service + Send>| s.resume_and_await())?;
+    let registered_names: HashSet<&'static str> = self.services.iter().map(|s| s.name()).collect();
+    self.dag.resume_and_await(&registered_names)
+  }
+
+}
@@ -1,55 +1,281 @@
 //! The key module of this library: a `Service`
 
+use crate::error::ServiceError;
+use crate::status::ServiceStatus;
+
 /// A `Service` is a _black box_ that does work: it can be started and it can be stopped.
 ///
 /// This trait abstracts away the actions that can be done from the outside to a `Service`.
 /// It's up to the specific implementor to make sense of what starting/stopping means.
 ///
-/// Note that every method in this trait is by default implemented as a no-op: this leaves to the
-/// actual implementor to decide what is fitting to implement, and what is not.
+/// Every lifecycle method returns `Result<(), ServiceError>`: a `Service` that fails to come up
+/// (or down, or pause, or resume) must report it, rather than leaving callers to guess from an
+/// unchanged status whether anything actually happened.
+///
+/// Note that every method in this trait, except `name()`, `start()` and `stop()`, has a default
+/// implementation: the `*_and_await()` methods call their two halves in sequence, `status()`
+/// reports `Stopped`, and the rest are no-ops. This leaves to the actual implementor to decide
+/// what is fitting to implement, and what is not.
+///
+/// If your `Service` cannot fail, implement [`TryService`](trait.TryService.html) instead: a
+/// blanket implementation of `Service` is provided for it.
 pub trait Service {
 
   /// Service name
   fn name(&self) -> &'static str;
 
   /// Starts the service
-  fn start(&mut self);
+  fn start(&mut self) -> Result<(), ServiceError>;
 
   /// Awaits that the service is done starting.
   ///
   /// Implement to provide sensible logic to wait for a service to be fully started.
   ///
   /// This is usually used _after_ a call to `start()`.
-  fn await_started(&mut self) {
+  fn await_started(&mut self) -> Result<(), ServiceError> {
     // By default, nothing to do
+    Ok(())
   }
 
   /// Starts the service and waits for it to be done starting.
   ///
   /// A _graceful_ start.
-  fn start_and_await(&mut self) {
-    self.start();
-    self.await_started();
+  fn start_and_await(&mut self) -> Result<(), ServiceError> {
+    self.start()?;
+    self.await_started()
   }
 
   /// Stops the service
-  fn stop(&mut self);
+  fn stop(&mut self) -> Result<(), ServiceError>;
 
   /// Awaits that the service is done stopping.
   ///
   /// Implement to provide sensible logic to wait for a service to be fully stopped.
   ///
   /// This is usually used _after_ a call to `stop()`.
-  fn await_stopped(&mut self) {
+  fn await_stopped(&mut self) -> Result<(), ServiceError> {
     // By default, nothing to do
+    Ok(())
   }
 
   /// Stops the service and waits for it to be done stopping.
   ///
   /// A _graceful_ stop.
+  fn stop_and_await(&mut self) -> Result<(), ServiceError> {
+    self.stop()?;
+    self.await_stopped()
+  }
+
+  /// Pauses the service
+  ///
+  /// Suspends the service's work without tearing it down: a paused service should be resumable
+  /// via `resume()` without re-doing whatever setup `start()` did.
+  fn pause(&mut self) -> Result<(), ServiceError> {
+    // By default, nothing to do
+    Ok(())
+  }
+
+  /// Awaits that the service is done pausing.
+  ///
+  /// Implement to provide sensible logic to wait for a service to be fully paused.
+  ///
+  /// This is usually used _after_ a call to `pause()`.
+  fn await_paused(&mut self) -> Result<(), ServiceError> {
+    // By default, nothing to do
+    Ok(())
+  }
+
+  /// Pauses the service and waits for it to be done pausing.
+  ///
+  /// A _graceful_ pause.
+  fn pause_and_await(&mut self) -> Result<(), ServiceError> {
+    self.pause()?;
+    self.await_paused()
+  }
+
+  /// Resumes the service
+  ///
+  /// Undoes a previous `pause()`, picking the service's work back up.
+  fn resume(&mut self) -> Result<(), ServiceError> {
+    // By default, nothing to do
+    Ok(())
+  }
+
+  /// Awaits that the service is done resuming.
+  ///
+  /// Implement to provide sensible logic to wait for a service to be fully resumed.
+  ///
+  /// This is usually used _after_ a call to `resume()`.
+  fn await_resumed(&mut self) -> Result<(), ServiceError> {
+    // By default, nothing to do
+    Ok(())
+  }
+
+  /// Resumes the service and waits for it to be done resuming.
+  ///
+  /// A _graceful_ resume.
+  fn resume_and_await(&mut self) -> Result<(), ServiceError> {
+    self.resume()?;
+    self.await_resumed()
+  }
+
+  /// The current status of the service.
+  ///
+  /// Implementors that track their lifecycle via a `ServiceStatusFlag` should override this to
+  /// return `flag.get_status()`. By default, a `Service` is assumed `Stopped`: a predictable
+  /// default, mirroring `ServiceStatusFlag`'s.
+  fn status(&self) -> ServiceStatus {
+    ServiceStatus::Stopped
+  }
+
+}
+
+/// Compatibility shim for `Service` implementations that cannot fail.
+///
+/// This mirrors the `Service` trait exactly, except every lifecycle method returns `()` instead
+/// of `Result<(), ServiceError>`. A blanket `impl<T: TryService> Service for T` is provided, so
+/// an implementor written against the old, infallible API keeps compiling unchanged: just
+/// implement `TryService` instead of `Service`.
+pub trait TryService {
+
+  /// Service name
+  fn name(&self) -> &'static str;
+
+  /// Starts the service
+  fn start(&mut self);
+
+  /// Awaits that the service is done starting.
+  fn await_started(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Starts the service and waits for it to be done starting.
+  fn start_and_await(&mut self) {
+    self.start();
+    self.await_started();
+  }
+
+  /// Stops the service
+  fn stop(&mut self);
+
+  /// Awaits that the service is done stopping.
+  fn await_stopped(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Stops the service and waits for it to be done stopping.
   fn stop_and_await(&mut self) {
     self.stop();
     self.await_stopped();
   }
 
-}
\ No newline at end of file
+  /// Pauses the service
+  fn pause(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Awaits that the service is done pausing.
+  fn await_paused(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Pauses the service and waits for it to be done pausing.
+  fn pause_and_await(&mut self) {
+    self.pause();
+    self.await_paused();
+  }
+
+  /// Resumes the service
+  fn resume(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Awaits that the service is done resuming.
+  fn await_resumed(&mut self) {
+    // By default, nothing to do
+  }
+
+  /// Resumes the service and waits for it to be done resuming.
+  fn resume_and_await(&mut self) {
+    self.resume();
+    self.await_resumed();
+  }
+
+  /// The current status of the service.
+  fn status(&self) -> ServiceStatus {
+    ServiceStatus::Stopped
+  }
+
+}
+
+impl<T: TryService> Service for T {
+
+  fn name(&self) -> &'static str {
+    TryService::name(self)
+  }
+
+  fn start(&mut self) -> Result<(), ServiceError> {
+    TryService::start(self);
+    Ok(())
+  }
+
+  fn await_started(&mut self) -> Result<(), ServiceError> {
+    TryService::await_started(self);
+    Ok(())
+  }
+
+  fn start_and_await(&mut self) -> Result<(), ServiceError> {
+    TryService::start_and_await(self);
+    Ok(())
+  }
+
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    TryService::stop(self);
+    Ok(())
+  }
+
+  fn await_stopped(&mut self) -> Result<(), ServiceError> {
+    TryService::await_stopped(self);
+    Ok(())
+  }
+
+  fn stop_and_await(&mut self) -> Result<(), ServiceError> {
+    TryService::stop_and_await(self);
+    Ok(())
+  }
+
+  fn pause(&mut self) -> Result<(), ServiceError> {
+    TryService::pause(self);
+    Ok(())
+  }
+
+  fn await_paused(&mut self) -> Result<(), ServiceError> {
+    TryService::await_paused(self);
+    Ok(())
+  }
+
+  fn pause_and_await(&mut self) -> Result<(), ServiceError> {
+    TryService::pause_and_await(self);
+    Ok(())
+  }
+
+  fn resume(&mut self) -> Result<(), ServiceError> {
+    TryService::resume(self);
+    Ok(())
+  }
+
+  fn await_resumed(&mut self) -> Result<(), ServiceError> {
+    TryService::await_resumed(self);
+    Ok(())
+  }
+
+  fn resume_and_await(&mut self) -> Result<(), ServiceError> {
+    TryService::resume_and_await(self);
+    Ok(())
+  }
+
+  fn status(&self) -> ServiceStatus {
+    TryService::status(self)
+  }
+
+}
@@ -2,23 +2,66 @@
 
 use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
 
+use event_listener::Event;
+
 /// The possible statuses of a `Service`
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ServiceStatus {
   Starting,
   Started,
   Stopping,
   Stopped,
+  Pausing,
+  Paused,
+  Resuming,
+}
+
+/// Error returned when a raw `usize` doesn't correspond to any `ServiceStatus`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidStatus(usize);
+
+impl InvalidStatus {
+  /// The raw value that didn't correspond to any `ServiceStatus`
+  pub fn raw(&self) -> usize {
+    self.0
+  }
+}
+
+impl std::fmt::Display for InvalidStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "There is no `ServiceStatus` that corresponds to {}", self.0)
+  }
+}
+
+impl std::error::Error for InvalidStatus {}
+
+impl ServiceStatus {
+
+  /// Never-panic counterpart to `From<usize>`.
+  ///
+  /// A corrupted atomic (or any other stray `usize`) yields an `InvalidStatus` instead of
+  /// unwinding: fatal for a library meant to back long-lived daemons.
+  pub fn try_from(raw_state: usize) -> Result<Self, InvalidStatus> {
+    match raw_state {
+      0x01 => Ok(ServiceStatus::Starting),
+      0x02 => Ok(ServiceStatus::Started),
+      0x04 => Ok(ServiceStatus::Stopping),
+      0x08 => Ok(ServiceStatus::Stopped),
+      0x10 => Ok(ServiceStatus::Pausing),
+      0x20 => Ok(ServiceStatus::Paused),
+      0x40 => Ok(ServiceStatus::Resuming),
+      _ => Err(InvalidStatus(raw_state)),
+    }
+  }
+
 }
 
 impl From<usize> for ServiceStatus {
+  /// A thin, panicking wrapper around `ServiceStatus::try_from`, kept for backward compatibility.
   fn from(raw_state: usize) -> Self {
-    match raw_state {
-      0x01 => ServiceStatus::Starting,
-      0x02 => ServiceStatus::Started,
-      0x04 => ServiceStatus::Stopping,
-      0x08 => ServiceStatus::Stopped,
-      _ => panic!("There is no `ServiceState` that corresponds to {}", raw_state),
+    match ServiceStatus::try_from(raw_state) {
+      Ok(status) => status,
+      Err(err) => panic!("{}", err),
     }
   }
 }
@@ -30,6 +73,9 @@ impl From<ServiceStatus> for usize {
       ServiceStatus::Started   => 0x02,
       ServiceStatus::Stopping  => 0x04,
       ServiceStatus::Stopped   => 0x08,
+      ServiceStatus::Pausing   => 0x10,
+      ServiceStatus::Paused    => 0x20,
+      ServiceStatus::Resuming  => 0x40,
     }
   }
 }
@@ -37,9 +83,10 @@ impl From<ServiceStatus> for usize {
 /// A flag that wraps the internal status of a `Service`, in a thread safe _envelope_.
 ///
 /// This should usually be stored as a field of a `Service` implementation
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ServiceStatusFlag {
   flag: Arc<AtomicUsize>,
+  event: Arc<Event>,
 }
 
 impl ServiceStatusFlag {
@@ -52,6 +99,7 @@ impl ServiceStatusFlag {
   pub fn new(status: ServiceStatus) -> Self {
     ServiceStatusFlag {
       flag: Arc::new(AtomicUsize::new(status.into())),
+      event: Arc::new(Event::new()),
     }
   }
 
@@ -61,8 +109,13 @@ impl ServiceStatusFlag {
   }
 
   /// Set the `ServiceStatus` wrapped by this flag
+  ///
+  /// This also wakes up every thread currently blocked in `await_started`/`await_stopped` (or
+  /// listening directly on this flag), so that there is never a need to spin-wait on a status
+  /// change.
   pub fn set_status(&self, status: ServiceStatus) {
-    self.flag.store(status.into(), Ordering::SeqCst)
+    self.flag.store(status.into(), Ordering::SeqCst);
+    self.event.notify(usize::MAX);
   }
 
   /// Set starting
@@ -118,24 +171,159 @@ impl ServiceStatusFlag {
     self.get_status() == ServiceStatus::Stopped
   }
 
+  /// Set pausing
+  ///
+  /// Usually used by a `Service` at the beginning of the `pause()` method: the `Service` hasn't
+  /// paused yet, but it's going through it's _suspend sequence_.
+  pub fn pausing(&self) {
+    self.set_status(ServiceStatus::Pausing)
+  }
+
+  /// Set paused
+  ///
+  /// Usually used by a `Service` at the end of the `pause()` logic.
+  pub fn paused(&self) {
+    self.set_status(ServiceStatus::Paused)
+  }
+
+  /// Set resuming
+  ///
+  /// Usually used by a `Service` at the beginning of the `resume()` method: the `Service` hasn't
+  /// resumed yet, but it's going through it's _resume sequence_.
+  pub fn resuming(&self) {
+    self.set_status(ServiceStatus::Resuming)
+  }
+
+  /// Is it pausing?
+  pub fn is_pausing(&self) -> bool {
+    self.get_status() == ServiceStatus::Pausing
+  }
+
+  /// Is it paused?
+  pub fn is_paused(&self) -> bool {
+    self.get_status() == ServiceStatus::Paused
+  }
+
+  /// Is it resuming?
+  pub fn is_resuming(&self) -> bool {
+    self.get_status() == ServiceStatus::Resuming
+  }
+
   /// Await started
   ///
-  /// This method **blocks** the current thread until the `ServiceStatus::Started` is set on
-  /// this instance by _another_ thread.
+  /// This method **blocks** the current thread, without spinning, until the
+  /// `ServiceStatus::Started` is set on this instance by _another_ thread.
+  ///
+  /// A listener is registered on the underlying event *before* the status is re-checked, so a
+  /// status change landing in between the first check and the registration is never missed.
   pub fn await_started(&self) {
-    while !self.is_started() {};
+    loop {
+      if self.is_started() {
+        return;
+      }
+      let listener = self.event.listen();
+      if self.is_started() {
+        return;
+      }
+      listener.wait();
+    }
   }
 
   /// Await stopped
   ///
-  /// This method **blocks** the current thread until the `ServiceStatus::Stopped` is set on
-  /// this instance by _another_ thread.
+  /// This method **blocks** the current thread, without spinning, until the
+  /// `ServiceStatus::Stopped` is set on this instance by _another_ thread.
+  ///
+  /// A listener is registered on the underlying event *before* the status is re-checked, so a
+  /// status change landing in between the first check and the registration is never missed.
   pub fn await_stopped(&self) {
-    while !self.is_stopped() {};
+    loop {
+      if self.is_stopped() {
+        return;
+      }
+      let listener = self.event.listen();
+      if self.is_stopped() {
+        return;
+      }
+      listener.wait();
+    }
+  }
+
+  /// Await paused
+  ///
+  /// This method **blocks** the current thread, without spinning, until the
+  /// `ServiceStatus::Paused` is set on this instance by _another_ thread.
+  ///
+  /// A listener is registered on the underlying event *before* the status is re-checked, so a
+  /// status change landing in between the first check and the registration is never missed.
+  pub fn await_paused(&self) {
+    loop {
+      if self.is_paused() {
+        return;
+      }
+      let listener = self.event.listen();
+      if self.is_paused() {
+        return;
+      }
+      listener.wait();
+    }
+  }
+
+  /// Await resumed
+  ///
+  /// This method **blocks** the current thread, without spinning, until the
+  /// `ServiceStatus::Started` is set on this instance by _another_ thread. This is the same as
+  /// `await_started`: it does not check that a `ServiceStatus::Resuming` came first, and returns
+  /// at once if the flag is already `Started`.
+  ///
+  /// A listener is registered on the underlying event *before* the status is re-checked, so a
+  /// status change landing in between the first check and the registration is never missed.
+  pub fn await_resumed(&self) {
+    self.await_started()
+  }
+
+  /// Get a cheaply-clonable [`StatusWatcher`](struct.StatusWatcher.html) observing this flag.
+  ///
+  /// Unlike the `await_*` methods above, which each wait for one specific status, a
+  /// `StatusWatcher` lets external code (a health endpoint, a dashboard, a supervisor) observe
+  /// the full progression of statuses over the life of the `Service`.
+  pub fn watcher(&self) -> StatusWatcher {
+    StatusWatcher {
+      flag: self.flag.clone(),
+      event: self.event.clone(),
+    }
+  }
+
+  /// Await started, by spinning
+  ///
+  /// A busy-wait alternative to [`await_started`](#method.await_started): it pins a CPU core at
+  /// 100% while waiting, but has no dependency on the underlying event primitive. Kept around for
+  /// no-dependency builds, behind the `spin-wait` feature.
+  #[cfg(feature = "spin-wait")]
+  pub fn await_started_spin(&self) {
+    while !self.is_started() {}
+  }
+
+  /// Await stopped, by spinning
+  ///
+  /// A busy-wait alternative to [`await_stopped`](#method.await_stopped): it pins a CPU core at
+  /// 100% while waiting, but has no dependency on the underlying event primitive. Kept around for
+  /// no-dependency builds, behind the `spin-wait` feature.
+  #[cfg(feature = "spin-wait")]
+  pub fn await_stopped_spin(&self) {
+    while !self.is_stopped() {}
   }
 
 }
 
+impl std::fmt::Debug for ServiceStatusFlag {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ServiceStatusFlag")
+      .field("status", &self.get_status())
+      .finish()
+  }
+}
+
 impl Default for ServiceStatusFlag {
 
   /// Creates a `ServiceStatusFlag` that is stopped: a predictable default.
@@ -144,3 +332,56 @@ impl Default for ServiceStatusFlag {
   }
 
 }
+
+/// A cheaply-clonable observer of a `ServiceStatusFlag`, obtained via
+/// [`ServiceStatusFlag::watcher`](struct.ServiceStatusFlag.html#method.watcher).
+///
+/// A `StatusWatcher` can only read the status it observes: it has no way to mutate it. This
+/// makes it safe to hand out to monitoring code that has no business starting or stopping the
+/// `Service` it watches.
+#[derive(Clone)]
+pub struct StatusWatcher {
+  flag: Arc<AtomicUsize>,
+  event: Arc<Event>,
+}
+
+impl StatusWatcher {
+
+  /// The status currently wrapped by the watched flag
+  pub fn current(&self) -> ServiceStatus {
+    self.flag.load(Ordering::SeqCst).into()
+  }
+
+  /// Block the current thread, without spinning, until the watched flag is set to exactly
+  /// `target`, returning once that happens.
+  ///
+  /// `ServiceStatus` values are independent bitmask constants, not a monotonically-increasing
+  /// timeline, so there is no general notion of "reached or passed" a target: this waits for the
+  /// flag to equal `target`, the same way the `await_*` methods on `ServiceStatusFlag` each wait
+  /// for one specific status.
+  ///
+  /// As with those `await_*` methods, a listener is registered on the underlying event *before*
+  /// the status is re-checked, so a status change landing in between the first check and the
+  /// registration is never missed.
+  pub fn wait_for(&self, target: ServiceStatus) -> ServiceStatus {
+    loop {
+      if self.current() == target {
+        return target;
+      }
+      let listener = self.event.listen();
+      if self.current() == target {
+        return target;
+      }
+      listener.wait();
+    }
+  }
+
+}
+
+impl std::fmt::Debug for StatusWatcher {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("StatusWatcher")
+      .field("status", &self.current())
+      .finish()
+  }
+}
@@ -0,0 +1,323 @@
+//! An opt-in supervision layer that restarts crashed `Service`s, with backoff
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use event_listener::Event;
+use log::*;
+
+use crate::error::ServiceError;
+use crate::service::Service;
+use crate::status::ServiceStatus;
+
+/// How eagerly a supervised `Service` should be restarted after it stops.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartMode {
+  /// Never restart: once stopped, the service is left as-is.
+  Never,
+  /// Restart unconditionally, whenever the service stops while the supervisor expects it to
+  /// still be running.
+  Always,
+  /// Restart only when the service stopped because of a failure.
+  ///
+  /// Note: today, the only failure signal a supervisor can observe from the outside is a
+  /// service reaching `Stopped` while it was supposed to still be running — the same signal
+  /// `Always` reacts to. The two modes are kept distinct so that `OnFailure` can be narrowed down
+  /// once `Service` exposes a finer-grained failure status.
+  OnFailure,
+}
+
+/// Caps how often, and how quickly, a supervised `Service` may be restarted.
+///
+/// # Parameters
+///
+/// * `mode`: when a restart should be attempted at all
+/// * `max_restarts`: the maximum number of restarts allowed within `window`, before the
+///   supervisor gives up and raises a fatal error
+/// * `window`: the rolling time window `max_restarts` is counted over
+/// * `base_delay`: the delay before the first restart attempt
+/// * `max_delay`: the cap the exponentially-doubling delay cannot exceed
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+  mode: RestartMode,
+  max_restarts: usize,
+  window: Duration,
+  base_delay: Duration,
+  max_delay: Duration,
+}
+
+impl RestartPolicy {
+
+  /// A policy that never restarts the service.
+  pub fn never() -> Self {
+    RestartPolicy {
+      mode: RestartMode::Never,
+      max_restarts: 0,
+      window: Duration::from_secs(0),
+      base_delay: Duration::from_secs(0),
+      max_delay: Duration::from_secs(0),
+    }
+  }
+
+  /// A policy that always restarts the service, within the given restart budget.
+  pub fn always(max_restarts: usize, window: Duration) -> Self {
+    RestartPolicy {
+      mode: RestartMode::Always,
+      max_restarts,
+      window,
+      base_delay: Duration::from_millis(100),
+      max_delay: Duration::from_secs(30),
+    }
+  }
+
+  /// A policy that restarts the service only on failure, within the given restart budget.
+  pub fn on_failure(max_restarts: usize, window: Duration) -> Self {
+    RestartPolicy {
+      mode: RestartMode::OnFailure,
+      max_restarts,
+      window,
+      base_delay: Duration::from_millis(100),
+      max_delay: Duration::from_secs(30),
+    }
+  }
+
+  /// Overrides the default exponential backoff schedule (`base_delay` doubling on every
+  /// consecutive restart, up to `max_delay`).
+  pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+    self.base_delay = base_delay;
+    self.max_delay = max_delay;
+    self
+  }
+
+  /// The delay to observe before the `attempt`-th restart (0-indexed), per the exponential
+  /// backoff schedule.
+  fn delay_for(&self, attempt: u32) -> Duration {
+    let scale = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    let millis = (self.base_delay.as_millis() as u64).saturating_mul(scale);
+    Duration::from_millis(millis).min(self.max_delay)
+  }
+
+}
+
+/// Wraps a `Service` so that `ServiceManager::register_supervised` can watch it, and restart it
+/// according to its `RestartPolicy`.
+///
+/// The wrapped service must be `Send`, because a background thread calls back into it whenever
+/// it needs to be restarted.
+pub struct Supervised {
+  name: &'static str,
+  inner: Arc<Mutex<Box<Service + Send>>>,
+  policy: RestartPolicy,
+  running: Arc<AtomicBool>,
+  stop_event: Arc<Event>,
+  // Set by the monitor thread for exactly the duration of `inner.start_and_await()`, so `stop()`
+  // knows not to block behind it (see `stop()` and `spawn_monitor`).
+  restarting: Arc<AtomicBool>,
+  // Latches once either `stop()` or the monitor thread has actually called `inner.stop()`, so the
+  // two can race to do it without calling it twice.
+  stop_performed: Arc<AtomicBool>,
+  monitor: Option<thread::JoinHandle<()>>,
+  fault: Arc<Mutex<Option<ServiceError>>>,
+}
+
+impl Supervised {
+
+  /// Constructor
+  pub fn new(service_box: Box<Service + Send>, policy: RestartPolicy) -> Self {
+    let name = service_box.as_ref().name();
+    Supervised {
+      name,
+      inner: Arc::new(Mutex::new(service_box)),
+      policy,
+      running: Arc::new(AtomicBool::new(false)),
+      stop_event: Arc::new(Event::new()),
+      restarting: Arc::new(AtomicBool::new(false)),
+      stop_performed: Arc::new(AtomicBool::new(false)),
+      monitor: None,
+      fault: Arc::new(Mutex::new(None)),
+    }
+  }
+
+  /// Takes the fatal error raised if this service's restart budget was exhausted, if any.
+  pub fn take_fault(&self) -> Option<ServiceError> {
+    self.fault.lock().unwrap().take()
+  }
+
+  /// A cheaply-clonable handle onto the fault slot filled in when the restart budget is
+  /// exhausted. Used by `ServiceManager` to aggregate faults across all supervised services.
+  pub(crate) fn fault_handle(&self) -> Arc<Mutex<Option<ServiceError>>> {
+    self.fault.clone()
+  }
+
+  fn spawn_monitor(&mut self) {
+    if let RestartMode::Never = self.policy.mode {
+      return;
+    }
+
+    let name = self.name;
+    let inner = self.inner.clone();
+    let policy = self.policy.clone();
+    let running = self.running.clone();
+    let stop_event = self.stop_event.clone();
+    let restarting = self.restarting.clone();
+    let stop_performed = self.stop_performed.clone();
+    let fault = self.fault.clone();
+
+    // Sleeps for at most `timeout`, waking early if `stop()` notifies `stop_event` in the
+    // meantime. Returns `false` as soon as `running` goes false, so an explicit stop always wins
+    // over whatever the monitor was about to do next (poll again, or restart after backoff).
+    fn wait_or_stop(running: &AtomicBool, stop_event: &Event, timeout: Duration) -> bool {
+      if !running.load(Ordering::SeqCst) {
+        return false;
+      }
+      let listener = stop_event.listen();
+      if !running.load(Ordering::SeqCst) {
+        return false;
+      }
+      listener.wait_timeout(timeout);
+      running.load(Ordering::SeqCst)
+    }
+
+    self.monitor = Some(thread::spawn(move || {
+      const POLL_INTERVAL: Duration = Duration::from_millis(25);
+      let mut restarts: Vec<Instant> = Vec::new();
+
+      loop {
+        if !wait_or_stop(&running, &stop_event, POLL_INTERVAL) {
+          // The manager stopped this service on purpose: nothing to supervise anymore.
+          return;
+        }
+
+        let is_stopped = inner.lock().unwrap().status() == ServiceStatus::Stopped;
+        if !is_stopped {
+          continue;
+        }
+
+        let now = Instant::now();
+        restarts.retain(|at| now.duration_since(*at) <= policy.window);
+
+        if restarts.len() >= policy.max_restarts {
+          error!("{} exhausted its restart budget ({} restarts within {:?}); giving up", name, policy.max_restarts, policy.window);
+          *fault.lock().unwrap() = Some(ServiceError::new(name, format!(
+            "exhausted restart budget of {} restart(s) within {:?}", policy.max_restarts, policy.window
+          )));
+          running.store(false, Ordering::SeqCst);
+          return;
+        }
+
+        let delay = policy.delay_for(restarts.len() as u32);
+        warn!("{} stopped unexpectedly; restarting in {:?} (attempt {}/{})", name, delay, restarts.len() + 1, policy.max_restarts);
+        if !wait_or_stop(&running, &stop_event, delay) {
+          // Stopped explicitly while backing off: an explicit stop always wins over a pending
+          // restart.
+          return;
+        }
+        restarts.push(now);
+
+        restarting.store(true, Ordering::SeqCst);
+        let mut guard = inner.lock().unwrap();
+        if let Err(err) = guard.start_and_await() {
+          error!("{} failed to restart: {}", name, err);
+        }
+        restarting.store(false, Ordering::SeqCst);
+
+        if !running.load(Ordering::SeqCst) && !stop_performed.swap(true, Ordering::SeqCst) {
+          // `stop()` was called while the restart above held `inner`'s lock, and deferred the
+          // actual `Service::stop()` call to us rather than block behind it (see `stop()`). Honor
+          // it now, while still holding the lock, before anyone else (e.g. `await_stopped()`) can
+          // observe the freshly-restarted-but-never-stopped service.
+          if let Err(err) = guard.stop() {
+            error!("{} failed to stop after a restart raced with stop(): {}", name, err);
+          }
+          return;
+        }
+      }
+    }));
+  }
+
+}
+
+impl Service for Supervised {
+
+  fn name(&self) -> &'static str {
+    self.name
+  }
+
+  fn start(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().start()
+  }
+
+  fn await_started(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().await_started()?;
+    self.running.store(true, Ordering::SeqCst);
+    self.stop_performed.store(false, Ordering::SeqCst);
+    self.restarting.store(false, Ordering::SeqCst);
+    self.spawn_monitor();
+    Ok(())
+  }
+
+  fn start_and_await(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().start_and_await()?;
+    self.running.store(true, Ordering::SeqCst);
+    self.stop_performed.store(false, Ordering::SeqCst);
+    self.restarting.store(false, Ordering::SeqCst);
+    self.spawn_monitor();
+    Ok(())
+  }
+
+  /// Stop the wrapped `Service`, and the monitor thread watching it.
+  ///
+  /// If a restart is in flight, this does not block for however long `Service::start_and_await`
+  /// takes: the monitor thread is watching `running` and will call `stop()` on the wrapped
+  /// `Service` itself, as soon as the restart attempt returns (see `spawn_monitor`), instead of
+  /// looping back to supervise a fresh restart. `await_stopped()` is what actually waits for that
+  /// to happen.
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    self.running.store(false, Ordering::SeqCst);
+    self.stop_event.notify(usize::MAX);
+
+    if self.restarting.load(Ordering::SeqCst) {
+      return Ok(());
+    }
+    if self.stop_performed.swap(true, Ordering::SeqCst) {
+      return Ok(());
+    }
+    self.inner.lock().unwrap().stop()
+  }
+
+  fn await_stopped(&mut self) -> Result<(), ServiceError> {
+    let result = self.inner.lock().unwrap().await_stopped();
+    if let Some(monitor) = self.monitor.take() {
+      let _ = monitor.join();
+    }
+    result
+  }
+
+  fn stop_and_await(&mut self) -> Result<(), ServiceError> {
+    self.stop()?;
+    self.await_stopped()
+  }
+
+  fn pause(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().pause()
+  }
+
+  fn await_paused(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().await_paused()
+  }
+
+  fn resume(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().resume()
+  }
+
+  fn await_resumed(&mut self) -> Result<(), ServiceError> {
+    self.inner.lock().unwrap().await_resumed()
+  }
+
+  fn status(&self) -> ServiceStatus {
+    self.inner.lock().unwrap().status()
+  }
+
+}
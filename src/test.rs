@@ -1,5 +1,9 @@
 use super::*;
-use std::{time::{Duration, Instant}, thread};
+use std::{
+  sync::{Arc, atomic::{AtomicUsize, Ordering}},
+  time::{Duration, Instant},
+  thread,
+};
 use crossbeam_channel::{Sender, bounded};
 
 struct ExampleService {
@@ -20,7 +24,7 @@ impl ExampleService {
   }
 }
 
-impl Service for ExampleService {
+impl TryService for ExampleService {
 
   fn name(&self) -> &'static str {
     "ExampleService"
@@ -65,6 +69,10 @@ impl Service for ExampleService {
     while !self.flag.is_stopped() {}
   }
 
+  fn status(&self) -> ServiceStatus {
+    self.flag.get_status()
+  }
+
 }
 
 #[test]
@@ -78,8 +86,8 @@ fn should_start_then_stop() {
   sm.register(Box::new(ExampleService::new("SB".to_string(), Duration::from_millis(sb_delay), sender.clone())));
 
   let now = Instant::now();
-  sm.start();
-  sm.await_started();
+  sm.start().unwrap();
+  sm.await_started().unwrap();
   let start_time = now.elapsed().as_millis();
   assert!(start_time >= sb_delay as u128);
   assert!(start_time < (sb_delay + sa_delay) as u128);
@@ -87,8 +95,8 @@ fn should_start_then_stop() {
   assert_eq!("Service SB STARTED", receiver.recv().unwrap());
 
   let now = Instant::now();
-  sm.stop();
-  sm.await_stopped();
+  sm.stop().unwrap();
+  sm.await_stopped().unwrap();
   let stop_time = now.elapsed().as_millis();
   assert!(stop_time >= sb_delay as u128);
   assert!(start_time < (sb_delay + sa_delay) as u128);
@@ -107,14 +115,344 @@ fn should_start_and_await_then_stop_and_await() {
   sm.register(Box::new(ExampleService::new("SB".to_string(), Duration::from_millis(sb_delay), sender.clone())));
 
   let now = Instant::now();
-  sm.start_and_await();
+  sm.start_and_await().unwrap();
   assert!(now.elapsed().as_millis() >= (sa_delay + sb_delay) as u128);
   assert_eq!("Service SA STARTED", receiver.recv().unwrap());
   assert_eq!("Service SB STARTED", receiver.recv().unwrap());
 
   let now = Instant::now();
-  sm.stop_and_await();
+  sm.stop_and_await().unwrap();
   assert!(now.elapsed().as_millis() >= (sa_delay + sb_delay) as u128);
   assert_eq!("Service SB STOPPED", receiver.recv().unwrap());
   assert_eq!("Service SA STOPPED", receiver.recv().unwrap());
 }
+
+#[test]
+fn should_not_return_immediately_for_a_status_not_yet_reached() {
+  // `Stopped` is the default status and has a higher raw bitmask value than `Started`; a
+  // `wait_for` that treated the statuses as an ordered timeline would return immediately here,
+  // instead of actually waiting for `Started` to be set.
+  let flag = ServiceStatusFlag::default();
+  let watcher = flag.watcher();
+  assert_eq!(watcher.current(), ServiceStatus::Stopped);
+
+  let background_flag = flag.clone();
+  thread::spawn(move || {
+    thread::sleep(Duration::from_millis(50));
+    background_flag.started();
+  });
+
+  let now = Instant::now();
+  assert_eq!(watcher.wait_for(ServiceStatus::Started), ServiceStatus::Started);
+  assert!(now.elapsed() >= Duration::from_millis(40));
+}
+
+/// A `Service` built directly on a `ServiceStatusFlag`, reporting every start and stop on
+/// `sender` and optionally failing to start or to stop, for exercising edge cases `ExampleService` above
+/// doesn't cover (pause/resume, rollback-on-failure, DAG scheduling).
+struct FlaggedService {
+  id: &'static str,
+  flag: ServiceStatusFlag,
+  fail_to_start: bool,
+  fail_to_stop: bool,
+  sender: Sender<String>,
+}
+
+impl FlaggedService {
+  fn new(id: &'static str, sender: Sender<String>) -> Self {
+    FlaggedService { id, flag: ServiceStatusFlag::default(), fail_to_start: false, fail_to_stop: false, sender }
+  }
+
+  fn failing(id: &'static str, sender: Sender<String>) -> Self {
+    FlaggedService { id, flag: ServiceStatusFlag::default(), fail_to_start: true, fail_to_stop: false, sender }
+  }
+
+  fn failing_to_stop(id: &'static str, sender: Sender<String>) -> Self {
+    FlaggedService { id, flag: ServiceStatusFlag::default(), fail_to_start: false, fail_to_stop: true, sender }
+  }
+}
+
+impl Service for FlaggedService {
+
+  fn name(&self) -> &'static str {
+    self.id
+  }
+
+  fn start(&mut self) -> Result<(), ServiceError> {
+    if self.fail_to_start {
+      return Err(ServiceError::new(self.id, "injected start failure"));
+    }
+    self.flag.started();
+    self.sender.send(format!("{} started", self.id)).unwrap();
+    Ok(())
+  }
+
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    self.flag.stopped();
+    self.sender.send(format!("{} stopped", self.id)).unwrap();
+    if self.fail_to_stop {
+      return Err(ServiceError::new(self.id, "injected stop failure"));
+    }
+    Ok(())
+  }
+
+  fn pause(&mut self) -> Result<(), ServiceError> {
+    self.flag.paused();
+    Ok(())
+  }
+
+  fn resume(&mut self) -> Result<(), ServiceError> {
+    self.flag.started();
+    Ok(())
+  }
+
+  fn status(&self) -> ServiceStatus {
+    self.flag.get_status()
+  }
+
+}
+
+#[test]
+fn should_pause_then_resume() {
+  let (sender, _receiver) = bounded(2);
+  let mut sm = ServiceManager::new();
+  sm.register(Box::new(FlaggedService::new("PauseMe", sender)));
+
+  sm.start_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("PauseMe", ServiceStatus::Started)]);
+
+  sm.pause_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("PauseMe", ServiceStatus::Paused)]);
+
+  sm.resume_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("PauseMe", ServiceStatus::Started)]);
+}
+
+#[test]
+fn should_roll_back_already_started_services_on_start_failure() {
+  let (sender, receiver) = bounded(2);
+  let mut sm = ServiceManager::new();
+  sm.register(Box::new(FlaggedService::new("First", sender.clone())));
+  sm.register(Box::new(FlaggedService::failing("Second", sender)));
+
+  let err = sm.start_and_await().unwrap_err();
+  assert_eq!(err.service(), "Second");
+  assert_eq!("First started", receiver.recv().unwrap());
+  assert_eq!("First stopped", receiver.recv().unwrap());
+}
+
+#[test]
+fn should_keep_stopping_remaining_services_after_a_mid_chain_stop_failure() {
+  let (sender, receiver) = bounded(4);
+  let mut sm = ServiceManager::new();
+  sm.register(Box::new(FlaggedService::new("First", sender.clone())));
+  sm.register(Box::new(FlaggedService::failing_to_stop("Second", sender)));
+
+  sm.start_and_await().unwrap();
+  assert_eq!("First started", receiver.recv().unwrap());
+  assert_eq!("Second started", receiver.recv().unwrap());
+
+  let err = sm.stop_and_await().unwrap_err();
+  assert_eq!(err.service(), "Second");
+  // "Second" is stopped first (reverse registration order) and fails; "First" must still get
+  // torn down rather than being left running behind the error.
+  assert_eq!("Second stopped", receiver.recv().unwrap());
+  assert_eq!("First stopped", receiver.recv().unwrap());
+}
+
+#[test]
+fn should_start_dag_service_after_its_plainly_registered_dependency() {
+  let (sender, receiver) = bounded(2);
+  let mut sm = ServiceManager::new();
+  sm.register(Box::new(FlaggedService::new("Base", sender.clone())));
+  sm.register_with_deps(Box::new(FlaggedService::new("Leaf", sender)), &["Base"]);
+
+  sm.start_and_await().unwrap();
+
+  assert_eq!("Base started", receiver.recv().unwrap());
+  assert_eq!("Leaf started", receiver.recv().unwrap());
+}
+
+#[test]
+fn should_pause_then_resume_dag_service_alongside_its_plainly_registered_dependency() {
+  let (sender, _receiver) = bounded(2);
+  let mut sm = ServiceManager::new();
+  sm.register(Box::new(FlaggedService::new("Base", sender.clone())));
+  sm.register_with_deps(Box::new(FlaggedService::new("Leaf", sender)), &["Base"]);
+
+  sm.start_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("Base", ServiceStatus::Started), ("Leaf", ServiceStatus::Started)]);
+
+  sm.pause_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("Base", ServiceStatus::Paused), ("Leaf", ServiceStatus::Paused)]);
+
+  sm.resume_and_await().unwrap();
+  assert_eq!(sm.statuses(), vec![("Base", ServiceStatus::Started), ("Leaf", ServiceStatus::Started)]);
+}
+
+#[test]
+fn should_reject_plain_start_and_stop_when_dag_services_are_registered() {
+  let (sender, _receiver) = bounded(1);
+  let mut sm = ServiceManager::new();
+  sm.register_with_deps(Box::new(FlaggedService::new("Leaf", sender)), &[]);
+
+  assert!(sm.start().is_err());
+  assert!(sm.stop().is_err());
+}
+
+#[test]
+fn should_detect_a_dependency_cycle() {
+  let (sender, _receiver) = bounded(2);
+  let mut sm = ServiceManager::new();
+  sm.register_with_deps(Box::new(FlaggedService::new("A", sender.clone())), &["B"]);
+  sm.register_with_deps(Box::new(FlaggedService::new("B", sender)), &["A"]);
+
+  let err = sm.start_and_await().unwrap_err();
+  assert!(err.message().contains("cycle"));
+}
+
+#[test]
+fn should_reject_dependency_on_a_truly_unregistered_service() {
+  let (sender, _receiver) = bounded(1);
+  let mut sm = ServiceManager::new();
+  sm.register_with_deps(Box::new(FlaggedService::new("Needs", sender)), &["Ghost"]);
+
+  let err = sm.start_and_await().unwrap_err();
+  assert!(err.message().contains("unregistered"));
+}
+
+/// A `Service` that reports itself `Stopped` shortly after every `start()`, to exercise
+/// `Supervised`'s restart-with-backoff loop deterministically.
+struct CrashingService {
+  flag: ServiceStatusFlag,
+  starts: Arc<AtomicUsize>,
+}
+
+impl CrashingService {
+  fn new(starts: Arc<AtomicUsize>) -> Self {
+    CrashingService { flag: ServiceStatusFlag::default(), starts }
+  }
+}
+
+impl Service for CrashingService {
+
+  fn name(&self) -> &'static str {
+    "Crasher"
+  }
+
+  fn start(&mut self) -> Result<(), ServiceError> {
+    self.starts.fetch_add(1, Ordering::SeqCst);
+    self.flag.started();
+
+    let flag = self.flag.clone();
+    thread::spawn(move || {
+      thread::sleep(Duration::from_millis(5));
+      flag.stopped();
+    });
+
+    Ok(())
+  }
+
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    self.flag.stopped();
+    Ok(())
+  }
+
+  fn status(&self) -> ServiceStatus {
+    self.flag.get_status()
+  }
+
+}
+
+/// A `Service` that takes `start_delay` to start, then reports itself `Stopped` shortly after, to
+/// exercise `Supervised::stop()` while a restart is in flight and still holding `inner`'s lock.
+struct SlowToStartService {
+  flag: ServiceStatusFlag,
+  starts: Arc<AtomicUsize>,
+  start_delay: Duration,
+}
+
+impl SlowToStartService {
+  fn new(starts: Arc<AtomicUsize>, start_delay: Duration) -> Self {
+    SlowToStartService { flag: ServiceStatusFlag::default(), starts, start_delay }
+  }
+}
+
+impl Service for SlowToStartService {
+
+  fn name(&self) -> &'static str {
+    "SlowToStart"
+  }
+
+  fn start(&mut self) -> Result<(), ServiceError> {
+    self.starts.fetch_add(1, Ordering::SeqCst);
+    thread::sleep(self.start_delay);
+    self.flag.started();
+
+    let flag = self.flag.clone();
+    thread::spawn(move || {
+      thread::sleep(Duration::from_millis(5));
+      flag.stopped();
+    });
+
+    Ok(())
+  }
+
+  fn stop(&mut self) -> Result<(), ServiceError> {
+    self.flag.stopped();
+    Ok(())
+  }
+
+  fn status(&self) -> ServiceStatus {
+    self.flag.get_status()
+  }
+
+}
+
+#[test]
+fn should_not_block_stop_behind_an_in_flight_restart() {
+  let starts = Arc::new(AtomicUsize::new(0));
+  let mut sm = ServiceManager::new();
+  let policy = RestartPolicy::always(100, Duration::from_secs(60))
+    .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+  sm.register_supervised(Box::new(SlowToStartService::new(starts.clone(), Duration::from_millis(1000))), policy);
+
+  sm.start_and_await().unwrap();
+  assert_eq!(starts.load(Ordering::SeqCst), 1);
+
+  // Give the crash a chance to happen and the monitor thread to commit to (slowly) restarting it.
+  thread::sleep(Duration::from_millis(100));
+  assert_eq!(starts.load(Ordering::SeqCst), 2, "the monitor should already be mid-restart by now");
+
+  let now = Instant::now();
+  sm.stop().unwrap();
+  assert!(now.elapsed() < Duration::from_millis(200), "stop() should not block behind the in-flight restart");
+
+  // await_stopped() is what's actually expected to wait, until the restart finishes and the
+  // monitor thread honors the stop it deferred.
+  sm.await_stopped().unwrap();
+}
+
+#[test]
+fn should_exhaust_restart_budget_and_report_a_fault() {
+  let starts = Arc::new(AtomicUsize::new(0));
+  let mut sm = ServiceManager::new();
+  let policy = RestartPolicy::always(2, Duration::from_secs(60))
+    .with_backoff(Duration::from_millis(5), Duration::from_millis(5));
+  sm.register_supervised(Box::new(CrashingService::new(starts.clone())), policy);
+
+  sm.start_and_await().unwrap();
+
+  let deadline = Instant::now() + Duration::from_secs(5);
+  let mut faults = Vec::new();
+  while faults.is_empty() && Instant::now() < deadline {
+    thread::sleep(Duration::from_millis(10));
+    faults = sm.supervision_faults();
+  }
+
+  assert_eq!(faults.len(), 1);
+  // One initial start, plus the 2 restarts the budget allows, before the supervisor gives up.
+  assert_eq!(starts.load(Ordering::SeqCst), 3);
+
+  sm.stop_and_await().unwrap();
+}
@@ -1,8 +1,41 @@
 //! Utilities to help you write services
 
+use std::error::Error;
+use std::fmt;
+
 use crossbeam_channel;
 use ctrlc;
 
+/// Error raised when the process termination signal cannot be waited for, e.g. because a
+/// `SIGINT`/`SIGTERM` handler is already registered elsewhere in the process.
+#[derive(Debug)]
+pub struct SignalError {
+  message: String,
+  source: Box<dyn Error + Send + Sync>,
+}
+
+impl SignalError {
+  fn new<M, E>(message: M, source: E) -> Self
+    where M: Into<String>, E: Into<Box<dyn Error + Send + Sync>> {
+    SignalError {
+      message: message.into(),
+      source: source.into(),
+    }
+  }
+}
+
+impl fmt::Display for SignalError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.message, self.source)
+  }
+}
+
+impl Error for SignalError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    Some(self.source.as_ref())
+  }
+}
+
 /// Block current thread, waiting for system termination signals (i.e. `SIGINT` / `SIGTERM`).
 ///
 /// When writing any kind of service that has to remain in execution, it will be probably necessary
@@ -13,14 +46,30 @@ use ctrlc;
 ///
 /// Just call this in your `main()` and add your "graceful termination logic" afterwards: it might
 /// be a bit _naive_, but it's simple and easy to use.
-pub fn wait_for_process_termination_signal() {
+///
+/// Returns an error rather than panicking if a handler cannot be registered (e.g. one is already
+/// registered elsewhere in the process) or cannot be waited for. See
+/// [`wait_for_process_termination_signal`](fn.wait_for_process_termination_signal.html) for a
+/// panicking wrapper around this.
+pub fn try_wait_for_process_termination_signal() -> Result<(), SignalError> {
   let (term_sender, term_receiver) = crossbeam_channel::bounded(1);
 
   // Register termination signal handler that sends a single message across the channel
   ctrlc::set_handler(move || {
-    term_sender.send(true).unwrap();
-  }).expect("Unable to define handler for SIGTERM/SIGINT");
+    let _ = term_sender.send(true);
+  }).map_err(|err| SignalError::new("unable to define handler for SIGTERM/SIGINT", err))?;
 
   // Block current thread until the single message is received across the channel
-  assert_eq!(term_receiver.iter().take(1).count(), 1, "Unable to handle SIGTERM/SIGINT");
+  term_receiver.recv()
+    .map(|_| ())
+    .map_err(|err| SignalError::new("unable to handle SIGTERM/SIGINT", err))
+}
+
+/// Block current thread, waiting for system termination signals (i.e. `SIGINT` / `SIGTERM`).
+///
+/// A thin, panicking wrapper around
+/// [`try_wait_for_process_termination_signal`](fn.try_wait_for_process_termination_signal.html),
+/// kept for backward compatibility.
+pub fn wait_for_process_termination_signal() {
+  try_wait_for_process_termination_signal().expect("Unable to handle SIGTERM/SIGINT")
 }